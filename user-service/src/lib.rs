@@ -0,0 +1,1358 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Error types for the user service
+#[derive(Error, Debug)]
+pub enum UserError {
+    #[error("User not found: {id}")]
+    NotFound { id: u64 },
+
+    #[error("Database error: {0}")]
+    DatabaseError(#[from] sqlx::Error),
+
+    #[error("Invalid user data: {reason}")]
+    ValidationError { reason: String },
+
+    #[error("Unauthorized: token is unknown or expired")]
+    Unauthorized,
+
+    #[error("Authentication failed: {reason}")]
+    AuthenticationFailed { reason: String },
+}
+
+/// User entity with serialization support
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+    pub email: String,
+    pub active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub token: Option<String>,
+}
+
+impl User {
+    /// Creates a new user instance
+    pub fn new(name: String, email: String) -> Self {
+        Self {
+            id: 0,
+            name,
+            email,
+            active: true,
+            created_at: chrono::Utc::now(),
+            token: None,
+        }
+    }
+
+    /// Validates user data
+    pub fn validate(&self) -> Result<(), UserError> {
+        if self.name.is_empty() {
+            return Err(UserError::ValidationError {
+                reason: "Name cannot be empty".to_string(),
+            });
+        }
+        if !self.email.contains('@') {
+            return Err(UserError::ValidationError {
+                reason: "Invalid email format".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Repository trait for user data access
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn find_by_id(&self, id: u64) -> Result<Option<User>, UserError>;
+    async fn find_all(&self) -> Result<Vec<User>, UserError>;
+    async fn find_by_token(&self, token: &str) -> Result<Option<User>, UserError>;
+    async fn find_page(&self, page: usize, per_page: usize) -> Result<Page<User>, UserError>;
+    async fn save(&self, user: &User) -> Result<u64, UserError>;
+    async fn delete(&self, id: u64) -> Result<(), UserError>;
+}
+
+/// Proof that `token` resolved to this specific logged-in user
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser(pub User);
+
+/// Maps a Postgres row to `User`, casting the signed `BIGINT` id column back to `u64`
+impl sqlx::FromRow<'_, sqlx::postgres::PgRow> for User {
+    fn from_row(row: &sqlx::postgres::PgRow) -> sqlx::Result<Self> {
+        use sqlx::Row;
+        let id: i64 = row.try_get("id")?;
+        Ok(User {
+            id: id as u64,
+            name: row.try_get("name")?,
+            email: row.try_get("email")?,
+            active: row.try_get("active")?,
+            created_at: row.try_get("created_at")?,
+            token: row.try_get("token")?,
+        })
+    }
+}
+
+/// `UserRepository` backed by a Postgres database via `sqlx`
+pub struct PostgresUserRepository {
+    pool: sqlx::PgPool,
+}
+
+impl PostgresUserRepository {
+    /// Creates a repository from an already-connected pool
+    pub fn new(pool: sqlx::PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepository for PostgresUserRepository {
+    async fn find_by_id(&self, id: u64) -> Result<Option<User>, UserError> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT id, name, email, active, created_at, token FROM users WHERE id = $1",
+        )
+        .bind(id as i64)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, UserError> {
+        let users = sqlx::query_as::<_, User>(
+            "SELECT id, name, email, active, created_at, token FROM users",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn find_by_token(&self, token: &str) -> Result<Option<User>, UserError> {
+        let user = sqlx::query_as::<_, User>(
+            "SELECT id, name, email, active, created_at, token FROM users WHERE token = $1",
+        )
+        .bind(token)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn find_page(&self, page: usize, per_page: usize) -> Result<Page<User>, UserError> {
+        let offset = page.saturating_sub(1) * per_page;
+
+        let items = sqlx::query_as::<_, User>(
+            "SELECT id, name, email, active, created_at, token FROM users ORDER BY id LIMIT $1 OFFSET $2",
+        )
+        .bind(per_page as i64)
+        .bind(offset as i64)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM users")
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok(Page::new(items, total as usize, page, per_page))
+    }
+
+    async fn save(&self, user: &User) -> Result<u64, UserError> {
+        // id 0 marks a user not stored yet: let the database assign its id
+        if user.id == 0 {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO users (name, email, active, created_at, token) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+            )
+            .bind(&user.name)
+            .bind(&user.email)
+            .bind(user.active)
+            .bind(user.created_at)
+            .bind(&user.token)
+            .fetch_one(&self.pool)
+            .await?;
+            return Ok(id as u64);
+        }
+
+        let id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO users (id, name, email, active, created_at, token)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO UPDATE SET
+                name = EXCLUDED.name,
+                email = EXCLUDED.email,
+                active = EXCLUDED.active,
+                token = EXCLUDED.token
+            RETURNING id
+            "#,
+        )
+        .bind(user.id as i64)
+        .bind(&user.name)
+        .bind(&user.email)
+        .bind(user.active)
+        .bind(user.created_at)
+        .bind(&user.token)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(id as u64)
+    }
+
+    async fn delete(&self, id: u64) -> Result<(), UserError> {
+        sqlx::query("DELETE FROM users WHERE id = $1")
+            .bind(id as i64)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// `UserRepository` backed by an in-memory map, for tests and local runs without a database
+pub struct InMemoryUserRepository {
+    users: Arc<Mutex<HashMap<u64, User>>>,
+    next_id: Arc<Mutex<u64>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self {
+            users: Arc::new(Mutex::new(HashMap::new())),
+            next_id: Arc::new(Mutex::new(1)),
+        }
+    }
+}
+
+impl Default for InMemoryUserRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn find_by_id(&self, id: u64) -> Result<Option<User>, UserError> {
+        Ok(self.users.lock().unwrap().get(&id).cloned())
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, UserError> {
+        Ok(self.users.lock().unwrap().values().cloned().collect())
+    }
+
+    async fn find_by_token(&self, token: &str) -> Result<Option<User>, UserError> {
+        Ok(self
+            .users
+            .lock()
+            .unwrap()
+            .values()
+            .find(|user| user.token.as_deref() == Some(token))
+            .cloned())
+    }
+
+    async fn find_page(&self, page: usize, per_page: usize) -> Result<Page<User>, UserError> {
+        let mut users: Vec<User> = self.users.lock().unwrap().values().cloned().collect();
+        users.sort_by_key(|user| user.id);
+
+        let total = users.len();
+        let offset = page.saturating_sub(1) * per_page;
+        let items = users.into_iter().skip(offset).take(per_page).collect();
+
+        Ok(Page::new(items, total, page, per_page))
+    }
+
+    async fn save(&self, user: &User) -> Result<u64, UserError> {
+        let mut users = self.users.lock().unwrap();
+        let id = if user.id == 0 {
+            let mut next_id = self.next_id.lock().unwrap();
+            let id = *next_id;
+            *next_id += 1;
+            id
+        } else {
+            user.id
+        };
+
+        let mut saved = user.clone();
+        saved.id = id;
+        users.insert(id, saved);
+
+        Ok(id)
+    }
+
+    async fn delete(&self, id: u64) -> Result<(), UserError> {
+        self.users.lock().unwrap().remove(&id);
+        Ok(())
+    }
+}
+
+/// Selects which storage backend `build_repository` wires up at startup
+pub enum RepositoryConfig {
+    InMemory,
+    Postgres { url: String, max_connections: u32 },
+    EventSourced { checkpoint_interval: u64 },
+}
+
+/// Builds the configured `UserRepository` backend, keeping storage selection
+/// out of `UserService::new`
+pub async fn build_repository(
+    config: RepositoryConfig,
+) -> Result<Arc<dyn UserRepository>, UserError> {
+    match config {
+        RepositoryConfig::InMemory => Ok(Arc::new(InMemoryUserRepository::new())),
+        RepositoryConfig::Postgres { url, max_connections } => {
+            let pool = sqlx::postgres::PgPoolOptions::new()
+                .max_connections(max_connections)
+                .connect(&url)
+                .await?;
+            Ok(Arc::new(PostgresUserRepository::new(pool)))
+        }
+        RepositoryConfig::EventSourced { checkpoint_interval } => {
+            Ok(Arc::new(EventSourcedUserRepository::new(checkpoint_interval)))
+        }
+    }
+}
+
+/// A single mutation recorded in an `EventSourcedUserRepository`'s append-only log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UserEvent {
+    Created { user: User },
+    Updated { user: User },
+    Deleted { id: u64 },
+}
+
+/// An event paired with its position in the log and when it was appended
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SequencedEvent {
+    sequence: u64,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    event: UserEvent,
+}
+
+/// A full-state snapshot the log was folded into up to `last_sequence`, so
+/// reconstruction only has to replay events newer than the checkpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Checkpoint {
+    last_sequence: u64,
+    state: HashMap<u64, User>,
+}
+
+/// `UserRepository` backed by an append-only event log instead of in-place
+/// mutation. `save`/`delete` append a `Created`/`Updated`/`Deleted` event;
+/// reads fold the log (on top of the latest checkpoint) to reconstruct
+/// current state. A checkpoint is written every `checkpoint_interval`
+/// appended events so replay cost stays bounded.
+///
+/// Invariant: sequence numbers are strictly increasing and gap-free, and a
+/// checkpoint always records the last sequence number it covers.
+pub struct EventSourcedUserRepository {
+    log: Mutex<Vec<SequencedEvent>>,
+    checkpoint: Mutex<Option<Checkpoint>>,
+    checkpoint_interval: u64,
+    next_id: Mutex<u64>,
+}
+
+impl EventSourcedUserRepository {
+    /// Creates a repository that checkpoints every `checkpoint_interval` appended events
+    pub fn new(checkpoint_interval: u64) -> Self {
+        Self {
+            log: Mutex::new(Vec::new()),
+            checkpoint: Mutex::new(None),
+            checkpoint_interval,
+            next_id: Mutex::new(1),
+        }
+    }
+
+    fn next_sequence(&self, log: &[SequencedEvent]) -> u64 {
+        match log.last() {
+            Some(last) => last.sequence + 1,
+            None => self
+                .checkpoint
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|checkpoint| checkpoint.last_sequence + 1)
+                .unwrap_or(0),
+        }
+    }
+
+    fn append(&self, event: UserEvent) {
+        let mut log = self.log.lock().unwrap();
+        let sequence = self.next_sequence(&log);
+        log.push(SequencedEvent {
+            sequence,
+            timestamp: chrono::Utc::now(),
+            event,
+        });
+
+        if log.len() as u64 >= self.checkpoint_interval {
+            let state = self.fold(&log);
+            let last_sequence = log.last().unwrap().sequence;
+            *self.checkpoint.lock().unwrap() = Some(Checkpoint { last_sequence, state });
+            log.clear();
+        }
+    }
+
+    /// Folds the latest checkpoint (if any) with the given events to produce current state
+    fn fold(&self, events: &[SequencedEvent]) -> HashMap<u64, User> {
+        let mut state = self
+            .checkpoint
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|checkpoint| checkpoint.state.clone())
+            .unwrap_or_default();
+
+        for sequenced in events {
+            match &sequenced.event {
+                UserEvent::Created { user } | UserEvent::Updated { user } => {
+                    state.insert(user.id, user.clone());
+                }
+                UserEvent::Deleted { id } => {
+                    state.remove(id);
+                }
+            }
+        }
+
+        state
+    }
+
+    fn current_state(&self) -> HashMap<u64, User> {
+        let log = self.log.lock().unwrap();
+        self.fold(&log)
+    }
+}
+
+#[async_trait]
+impl UserRepository for EventSourcedUserRepository {
+    async fn find_by_id(&self, id: u64) -> Result<Option<User>, UserError> {
+        Ok(self.current_state().remove(&id))
+    }
+
+    async fn find_all(&self) -> Result<Vec<User>, UserError> {
+        Ok(self.current_state().into_values().collect())
+    }
+
+    async fn find_by_token(&self, token: &str) -> Result<Option<User>, UserError> {
+        Ok(self
+            .current_state()
+            .into_values()
+            .find(|user| user.token.as_deref() == Some(token)))
+    }
+
+    async fn find_page(&self, page: usize, per_page: usize) -> Result<Page<User>, UserError> {
+        let mut users: Vec<User> = self.current_state().into_values().collect();
+        users.sort_by_key(|user| user.id);
+
+        let total = users.len();
+        let offset = page.saturating_sub(1) * per_page;
+        let items = users.into_iter().skip(offset).take(per_page).collect();
+
+        Ok(Page::new(items, total, page, per_page))
+    }
+
+    async fn save(&self, user: &User) -> Result<u64, UserError> {
+        let state = self.current_state();
+        let id = if user.id == 0 {
+            // Deriving ids from currently-live keys would reassign a deleted
+            // user's id to an unrelated new user, making the replayed history
+            // look like one entity was resurrected. A separate monotonic
+            // counter guarantees ids are never reused, deleted or not.
+            let mut next_id = self.next_id.lock().unwrap();
+            let id = *next_id;
+            *next_id += 1;
+            id
+        } else {
+            user.id
+        };
+
+        let mut saved = user.clone();
+        saved.id = id;
+        let event = if state.contains_key(&id) {
+            UserEvent::Updated { user: saved }
+        } else {
+            UserEvent::Created { user: saved }
+        };
+        self.append(event);
+
+        Ok(id)
+    }
+
+    async fn delete(&self, id: u64) -> Result<(), UserError> {
+        self.append(UserEvent::Deleted { id });
+        Ok(())
+    }
+}
+
+/// An authentication source, separate from storage: resolves a username/password
+/// pair to a `User` without saying anything about where that user is persisted
+/// or issuing a bearer token — use `UserService::login` to turn a verified
+/// credential into a token `authenticate` can later resolve.
+#[async_trait]
+pub trait LoginProvider: Send + Sync {
+    async fn login(&self, username: &str, password: &str) -> Result<User, UserError>;
+}
+
+/// A single bootstrap/test credential entry for `StaticLoginProvider`
+#[derive(Clone)]
+pub struct StaticCredential {
+    pub username: String,
+    pub email: String,
+    pub password_hash: String,
+}
+
+/// Authenticates against a fixed, configured list of credentials — useful for
+/// bootstrap admin accounts and tests that don't want a directory dependency
+pub struct StaticLoginProvider {
+    credentials: Vec<StaticCredential>,
+}
+
+impl StaticLoginProvider {
+    pub fn new(credentials: Vec<StaticCredential>) -> Self {
+        Self { credentials }
+    }
+}
+
+#[async_trait]
+impl LoginProvider for StaticLoginProvider {
+    async fn login(&self, username: &str, password: &str) -> Result<User, UserError> {
+        let credential = self
+            .credentials
+            .iter()
+            .find(|credential| credential.username == username)
+            .ok_or_else(|| UserError::AuthenticationFailed {
+                reason: "unknown username".to_string(),
+            })?;
+
+        if !verify_password(password, &credential.password_hash) {
+            return Err(UserError::AuthenticationFailed {
+                reason: "incorrect password".to_string(),
+            });
+        }
+
+        Ok(User::new(credential.username.clone(), credential.email.clone()))
+    }
+}
+
+fn verify_password(password: &str, password_hash: &str) -> bool {
+    use sha2::{Digest, Sha256};
+    let hashed = format!("{:x}", Sha256::digest(password.as_bytes()));
+    hashed == password_hash
+}
+
+/// Generates an opaque bearer token for a freshly logged-in user
+fn generate_token() -> String {
+    use rand::Rng;
+    let bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Resolves `email`/`name` against an existing row (reusing its id) or a fresh
+/// `User`, without persisting — callers set any further fields (e.g. a freshly
+/// issued token) and call `save` themselves. A blank email is never treated as
+/// a match: a provider that can't resolve one (e.g. an LDAP entry with no
+/// `mail` attribute) would otherwise collide with every other blank-email login.
+async fn resolve_by_email(
+    repository: &dyn UserRepository,
+    name: String,
+    email: String,
+) -> Result<User, UserError> {
+    let existing = if email.is_empty() {
+        None
+    } else {
+        repository
+            .find_all()
+            .await?
+            .into_iter()
+            .find(|user| user.email == email)
+    };
+
+    let mut user = existing.unwrap_or_else(|| User::new(name.clone(), email.clone()));
+    user.name = name;
+    user.email = email;
+    Ok(user)
+}
+
+/// Authenticates against an LDAP directory: connects, searches for the user DN
+/// without a prior bind (anonymously), attempts a bind with the supplied
+/// password, and maps the resolved
+/// attributes (cn→name, mail→email) into a `User`
+pub struct LdapLoginProvider<R: UserRepository> {
+    server_url: String,
+    base_dn: String,
+    user_filter: String,
+    repository: Arc<R>,
+}
+
+impl<R: UserRepository> LdapLoginProvider<R> {
+    pub fn new(server_url: String, base_dn: String, user_filter: String, repository: Arc<R>) -> Self {
+        Self {
+            server_url,
+            base_dn,
+            user_filter,
+            repository,
+        }
+    }
+}
+
+#[async_trait]
+impl<R: UserRepository> LoginProvider for LdapLoginProvider<R> {
+    async fn login(&self, username: &str, password: &str) -> Result<User, UserError> {
+        let (conn, mut ldap) = ldap3::LdapConnAsync::new(&self.server_url)
+            .await
+            .map_err(|err| UserError::AuthenticationFailed {
+                reason: format!("could not reach LDAP server: {err}"),
+            })?;
+        ldap3::drive!(conn);
+
+        let filter = self.user_filter.replace("{username}", username);
+        let (results, _) = ldap
+            .search(&self.base_dn, ldap3::Scope::Subtree, &filter, vec!["cn", "mail"])
+            .await
+            .and_then(|response| response.success())
+            .map_err(|err| UserError::AuthenticationFailed {
+                reason: format!("LDAP search failed: {err}"),
+            })?;
+
+        let entry = results
+            .into_iter()
+            .next()
+            .ok_or_else(|| UserError::AuthenticationFailed {
+                reason: "no such user in directory".to_string(),
+            })?;
+        let entry = ldap3::SearchEntry::construct(entry);
+
+        ldap.simple_bind(&entry.dn, password)
+            .await
+            .and_then(|response| response.success())
+            .map_err(|_| UserError::AuthenticationFailed {
+                reason: "directory rejected credentials".to_string(),
+            })?;
+
+        let name = first_attr(&entry, "cn").unwrap_or_else(|| username.to_string());
+        let email = first_attr(&entry, "mail").unwrap_or_default();
+
+        // Not validate()'d: a directory entry with no `mail` attribute is a
+        // normal occurrence here, and this provider has never required a
+        // synced user to carry a well-formed email.
+        let mut user = resolve_by_email(self.repository.as_ref(), name, email).await?;
+        user.id = self.repository.save(&user).await?;
+
+        Ok(user)
+    }
+}
+
+fn first_attr(entry: &ldap3::SearchEntry, name: &str) -> Option<String> {
+    entry.attrs.get(name)?.first().cloned()
+}
+
+/// Generic TTL + capacity-bounded cache, reusable for any `Cacheable` entity
+/// keyed by `K` — not just `User`. When a fresh insert would exceed
+/// `max_size`, the least-recently-used key is evicted. `entries` and
+/// `access_order` must stay in sync on every insert, touch, and eviction.
+pub struct CacheLayer<K, V> {
+    entries: HashMap<K, (V, Instant)>,
+    access_order: VecDeque<K>,
+    max_size: usize,
+    ttl: Duration,
+}
+
+impl<K: Eq + std::hash::Hash + Clone, V: Clone> CacheLayer<K, V> {
+    pub fn new(max_size: usize, ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            access_order: VecDeque::new(),
+            max_size,
+            ttl,
+        }
+    }
+
+    /// Returns the cached value if present and not expired, marking it most-recently-used.
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let expired = match self.entries.get(key) {
+            Some((_, inserted_at)) => inserted_at.elapsed() > self.ttl,
+            None => return None,
+        };
+
+        if expired {
+            self.remove(key);
+            return None;
+        }
+
+        self.touch(key.clone());
+        self.entries.get(key).map(|(value, _)| value.clone())
+    }
+
+    /// Inserts a value, returning the entry this displaced, if any: the prior
+    /// value at this key (if overwritten) or else the least-recently-used
+    /// entry evicted because capacity was exceeded.
+    pub fn insert(&mut self, key: K, value: V) -> Vec<(K, V)> {
+        let mut displaced = Vec::new();
+
+        if let Some(old_value) = self.remove(&key) {
+            displaced.push((key.clone(), old_value));
+        } else if self.max_size > 0 && self.entries.len() >= self.max_size {
+            if let Some(lru_key) = self.access_order.pop_front() {
+                if let Some((lru_value, _)) = self.entries.remove(&lru_key) {
+                    displaced.push((lru_key, lru_value));
+                }
+            }
+        }
+
+        self.entries.insert(key.clone(), (value, Instant::now()));
+        self.access_order.push_back(key);
+        displaced
+    }
+
+    /// Removes a single entry, returning its value if it was present.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let removed = self.entries.remove(key).map(|(value, _)| value);
+        self.access_order.retain(|cached_key| cached_key != key);
+        removed
+    }
+
+    fn touch(&mut self, key: K) {
+        self.access_order.retain(|cached_key| cached_key != &key);
+        self.access_order.push_back(key);
+    }
+
+    pub fn invalidate(&mut self, key: &K) {
+        self.remove(key);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.access_order.clear();
+    }
+}
+
+impl<K: Eq + std::hash::Hash + Clone, V: Clone> Cacheable for CacheLayer<K, V> {
+    type Key = K;
+    type Value = V;
+
+    /// Returns the cached value if present and not expired; an expired entry
+    /// reads as a miss but stays stored until the next `&mut` access.
+    fn get(&self, key: &Self::Key) -> Option<&Self::Value> {
+        self.entries
+            .get(key)
+            .filter(|(_, inserted_at)| inserted_at.elapsed() <= self.ttl)
+            .map(|(value, _)| value)
+    }
+
+    fn set(&mut self, key: Self::Key, value: Self::Value) {
+        self.insert(key, value);
+    }
+}
+
+/// Decides the cache key for a value, or `None` to skip caching it entirely —
+/// lets callers supply their own caching policy independent of `CacheLayer`.
+pub trait CacheIssuer<I> {
+    type Key;
+
+    fn key(&self, input: &I) -> Option<Self::Key>;
+}
+
+/// Default issuer: every user is cacheable, keyed by id
+#[derive(Default)]
+pub struct IdCacheIssuer;
+
+impl CacheIssuer<User> for IdCacheIssuer {
+    type Key = u64;
+
+    fn key(&self, input: &User) -> Option<u64> {
+        Some(input.id)
+    }
+}
+
+/// Issuer that declines to cache inactive users
+#[derive(Default)]
+pub struct ActiveOnlyCacheIssuer;
+
+impl CacheIssuer<User> for ActiveOnlyCacheIssuer {
+    type Key = u64;
+
+    fn key(&self, input: &User) -> Option<u64> {
+        input.active.then_some(input.id)
+    }
+}
+
+/// User cache: a `CacheLayer<u64, User>` plus a token→id index kept in
+/// lockstep so bearer tokens resolve to a cached `User` without a DB
+/// round-trip.
+struct UserCache {
+    by_id: CacheLayer<u64, User>,
+    by_token: HashMap<String, u64>,
+}
+
+impl UserCache {
+    fn new(max_size: usize, ttl: Duration) -> Self {
+        Self {
+            by_id: CacheLayer::new(max_size, ttl),
+            by_token: HashMap::new(),
+        }
+    }
+
+    /// Returns the cached user if present and not expired, marking it most-recently-used.
+    /// A miss here can mean the entry just expired inside `by_id`, so also purge
+    /// any `by_token` entry still pointing at `id` to keep the two indexes in lockstep.
+    fn get(&mut self, id: u64) -> Option<User> {
+        let user = CacheLayer::get(&mut self.by_id, &id);
+        if user.is_none() {
+            self.by_token.retain(|_, cached_id| *cached_id != id);
+        }
+        user
+    }
+
+    /// Returns the cached user for a bearer token if present and not expired.
+    fn get_by_token(&mut self, token: &str) -> Option<User> {
+        let id = *self.by_token.get(token)?;
+        self.get(id)
+    }
+
+    /// Inserts or refreshes an entry, evicting the least-recently-used key if over capacity.
+    fn insert(&mut self, id: u64, user: User) {
+        let new_token = user.token.clone();
+
+        for (_, displaced_user) in self.by_id.insert(id, user) {
+            // Only drop a displaced token mapping if it's not the one we're about
+            // to (re)insert below — otherwise refreshing an entry with an unchanged
+            // token would insert the mapping and then immediately remove it again.
+            if displaced_user.token != new_token {
+                if let Some(token) = &displaced_user.token {
+                    self.by_token.remove(token);
+                }
+            }
+        }
+
+        if let Some(token) = new_token {
+            self.by_token.insert(token, id);
+        }
+    }
+
+    /// Removes a single entry, e.g. after a write that would otherwise leave a stale read.
+    fn invalidate(&mut self, id: u64) {
+        if let Some(user) = self.by_id.remove(&id) {
+            if let Some(token) = &user.token {
+                self.by_token.remove(token);
+            }
+        }
+    }
+
+    /// Drops every cached entry.
+    fn clear(&mut self) {
+        self.by_id.clear();
+        self.by_token.clear();
+    }
+}
+
+/// User service implementation. The `I` type param decides, per fetched user,
+/// whether and under what key it gets cached — callers that don't care use
+/// the default `IdCacheIssuer`.
+pub struct UserService<R: UserRepository, I: CacheIssuer<User, Key = u64> = IdCacheIssuer> {
+    repository: Arc<R>,
+    cache: Arc<Mutex<UserCache>>,
+    config: ServiceConfig,
+    issuer: I,
+}
+
+/// Service configuration
+#[derive(Clone)]
+pub struct ServiceConfig {
+    pub cache_enabled: bool,
+    pub max_cache_size: usize,
+    pub timeout_secs: u64,
+    pub max_page_size: usize,
+}
+
+impl Default for ServiceConfig {
+    fn default() -> Self {
+        Self {
+            cache_enabled: true,
+            max_cache_size: 1000,
+            timeout_secs: 30,
+            max_page_size: 100,
+        }
+    }
+}
+
+impl<R: UserRepository> UserService<R, IdCacheIssuer> {
+    /// Creates a new service instance that caches every fetched user by id
+    pub fn new(repository: R) -> Self {
+        Self::with_issuer(repository, IdCacheIssuer)
+    }
+}
+
+impl<R: UserRepository, I: CacheIssuer<User, Key = u64>> UserService<R, I> {
+    /// Creates a service instance with a custom cache issuer, e.g. one that
+    /// declines to cache inactive users
+    pub fn with_issuer(repository: R, issuer: I) -> Self {
+        let config = ServiceConfig::default();
+        Self {
+            repository: Arc::new(repository),
+            cache: Arc::new(Mutex::new(UserCache::new(
+                config.max_cache_size,
+                Duration::from_secs(config.timeout_secs),
+            ))),
+            config,
+            issuer,
+        }
+    }
+
+    /// Caches `user` under whatever key `self.issuer` assigns it, if any
+    fn cache_user(&self, user: &User) {
+        if !self.config.cache_enabled {
+            return;
+        }
+        if let Some(key) = self.issuer.key(user) {
+            if let Ok(mut cache) = self.cache.lock() {
+                cache.insert(key, user.clone());
+            }
+        }
+    }
+
+    /// Gets a user by ID with caching
+    pub async fn get_user(&self, id: u64) -> Result<User, UserError> {
+        // Check cache first; an expired entry is treated as a miss and dropped
+        if self.config.cache_enabled {
+            if let Ok(mut cache) = self.cache.lock() {
+                if let Some(user) = cache.get(id) {
+                    return Ok(user);
+                }
+            }
+        }
+
+        // Fetch from repository
+        let user = self.repository
+            .find_by_id(id)
+            .await?
+            .ok_or(UserError::NotFound { id })?;
+
+        self.cache_user(&user);
+
+        Ok(user)
+    }
+
+    /// Creates a new user
+    pub async fn create_user(&self, name: String, email: String) -> Result<u64, UserError> {
+        let user = User::new(name, email);
+        user.validate()?;
+        let id = self.repository.save(&user).await?;
+
+        // Purge any stale entry under the freshly assigned id
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.invalidate(id);
+        }
+
+        Ok(id)
+    }
+
+    /// Verifies credentials via `provider`, then issues and persists a fresh bearer
+    /// token against the matching local user record (created by email if none exists
+    /// yet), so a later `authenticate(token)` call can resolve it without going back
+    /// through `provider`.
+    ///
+    /// Like `create_user`, this requires the resolved identity to carry a valid
+    /// email: a provider whose directory entry has none (e.g. `LdapLoginProvider`
+    /// against an entry with no `mail` attribute) can still authenticate via
+    /// `provider.login` directly, but won't be issued a token through here.
+    pub async fn login(
+        &self,
+        provider: &dyn LoginProvider,
+        username: &str,
+        password: &str,
+    ) -> Result<AuthenticatedUser, UserError> {
+        let authenticated = provider.login(username, password).await?;
+
+        // If the provider already persisted this user (e.g. LdapLoginProvider's
+        // upsert), reuse that record and its id instead of looking it up again
+        // by email.
+        let mut user = if authenticated.id != 0 {
+            authenticated
+        } else {
+            resolve_by_email(self.repository.as_ref(), authenticated.name, authenticated.email)
+                .await?
+        };
+        user.validate()?;
+        user.token = Some(generate_token());
+        user.id = self.repository.save(&user).await?;
+
+        self.cache_user(&user);
+
+        Ok(AuthenticatedUser(user))
+    }
+
+    /// Resolves a bearer token to the logged-in user it belongs to, preferring the
+    /// token index in cache over a repository round-trip.
+    pub async fn authenticate(&self, token: &str) -> Result<AuthenticatedUser, UserError> {
+        if self.config.cache_enabled {
+            if let Ok(mut cache) = self.cache.lock() {
+                if let Some(user) = cache.get_by_token(token) {
+                    return Ok(AuthenticatedUser(user));
+                }
+            }
+        }
+
+        let user = self
+            .repository
+            .find_by_token(token)
+            .await?
+            .ok_or(UserError::Unauthorized)?;
+
+        self.cache_user(&user);
+
+        Ok(AuthenticatedUser(user))
+    }
+
+    /// Deletes a user, purging it from the cache so later reads don't see stale data
+    pub async fn delete_user(&self, id: u64) -> Result<(), UserError> {
+        self.repository.delete(id).await?;
+
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.invalidate(id);
+        }
+
+        Ok(())
+    }
+
+    /// Drops every cached entry, e.g. after a bulk reload of the backing store
+    /// that would otherwise leave the cache pointing at stale data.
+    pub fn clear_cache(&self) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.clear();
+        }
+    }
+
+    /// Lists users a page at a time instead of loading the whole table.
+    /// `page` is 1-indexed and `per_page` must be nonzero; `per_page` is
+    /// clamped to `config.max_page_size`.
+    pub async fn list_users(&self, page: usize, per_page: usize) -> Result<Page<User>, UserError> {
+        if page == 0 {
+            return Err(UserError::ValidationError {
+                reason: "page must be at least 1".to_string(),
+            });
+        }
+        if per_page == 0 {
+            return Err(UserError::ValidationError {
+                reason: "per_page must be nonzero".to_string(),
+            });
+        }
+
+        let per_page = per_page.min(self.config.max_page_size);
+        self.repository.find_page(page, per_page).await
+    }
+}
+
+/// Generic pagination structure. `page` is 1-indexed, matching `find_page`'s convention.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+    pub page: usize,
+    pub per_page: usize,
+}
+
+impl<T> Page<T> {
+    pub fn new(items: Vec<T>, total: usize, page: usize, per_page: usize) -> Self {
+        Self {
+            items,
+            total,
+            page,
+            per_page,
+        }
+    }
+
+    pub fn has_next(&self) -> bool {
+        self.page * self.per_page < self.total
+    }
+}
+
+/// Trait with associated types
+pub trait Cacheable {
+    type Key;
+    type Value;
+
+    fn get(&self, key: &Self::Key) -> Option<&Self::Value>;
+    fn set(&mut self, key: Self::Key, value: Self::Value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_validation() {
+        let user = User::new("John".to_string(), "john@example.com".to_string());
+        assert!(user.validate().is_ok());
+
+        let invalid_user = User::new("".to_string(), "invalid".to_string());
+        assert!(invalid_user.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_service_creation() {
+        let service = UserService::new(InMemoryUserRepository::new());
+        let id = service
+            .create_user("Jane".to_string(), "jane@example.com".to_string())
+            .await
+            .unwrap();
+
+        let user = service.get_user(id).await.unwrap();
+        assert_eq!(user.name, "Jane");
+    }
+
+    #[tokio::test]
+    async fn test_list_users_rejects_page_zero() {
+        let service = UserService::new(InMemoryUserRepository::new());
+        service
+            .create_user("Jane".to_string(), "jane@example.com".to_string())
+            .await
+            .unwrap();
+
+        assert!(service.list_users(0, 10).await.is_err());
+
+        let page = service.list_users(1, 10).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert!(!page.has_next());
+    }
+
+    #[tokio::test]
+    async fn test_login_issues_token_that_authenticate_can_resolve() {
+        use sha2::{Digest, Sha256};
+
+        let password_hash = format!("{:x}", Sha256::digest(b"hunter2"));
+        let provider = StaticLoginProvider::new(vec![StaticCredential {
+            username: "jane".to_string(),
+            email: "jane@example.com".to_string(),
+            password_hash,
+        }]);
+
+        let service = UserService::new(InMemoryUserRepository::new());
+        let logged_in = service.login(&provider, "jane", "hunter2").await.unwrap();
+        let token = logged_in.0.token.clone().expect("login must issue a token");
+
+        let authenticated = service.authenticate(&token).await.unwrap();
+        assert_eq!(authenticated.0.email, "jane@example.com");
+
+        // Logging in again must update the same record, not insert a duplicate.
+        service.login(&provider, "jane", "hunter2").await.unwrap();
+        assert_eq!(
+            service
+                .repository
+                .find_all()
+                .await
+                .unwrap()
+                .iter()
+                .filter(|user| user.email == "jane@example.com")
+                .count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_by_email_never_matches_on_blank_email() {
+        let repository = InMemoryUserRepository::new();
+
+        let alice = resolve_by_email(&repository, "alice".to_string(), String::new())
+            .await
+            .unwrap();
+        alice.validate().unwrap_err(); // blank email: caller decides whether to reject
+        repository.save(&alice).await.unwrap();
+
+        let bob = resolve_by_email(&repository, "bob".to_string(), String::new())
+            .await
+            .unwrap();
+
+        assert_ne!(alice.name, bob.name);
+        assert_eq!(bob.id, 0, "a blank-email lookup must never reuse another user's row");
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_repository_crud() {
+        let repo = InMemoryUserRepository::new();
+
+        let first = User::new("Alice".to_string(), "alice@example.com".to_string());
+        let first_id = repo.save(&first).await.unwrap();
+        let second = User::new("Bob".to_string(), "bob@example.com".to_string());
+        let second_id = repo.save(&second).await.unwrap();
+        assert_ne!(first_id, second_id);
+
+        let fetched = repo.find_by_id(first_id).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "Alice");
+        assert_eq!(repo.find_all().await.unwrap().len(), 2);
+
+        let mut renamed = fetched.clone();
+        renamed.name = "Alicia".to_string();
+        repo.save(&renamed).await.unwrap();
+        assert_eq!(
+            repo.find_by_id(first_id).await.unwrap().unwrap().name,
+            "Alicia"
+        );
+
+        repo.delete(second_id).await.unwrap();
+        assert!(repo.find_by_id(second_id).await.unwrap().is_none());
+        assert_eq!(repo.find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_memory_repository_find_page() {
+        let repo = InMemoryUserRepository::new();
+        for i in 0..5 {
+            repo.save(&User::new(format!("User{i}"), format!("user{i}@example.com")))
+                .await
+                .unwrap();
+        }
+
+        let page = repo.find_page(1, 2).await.unwrap();
+        assert_eq!(page.total, 5);
+        assert_eq!(page.items.len(), 2);
+        assert!(page.has_next());
+
+        let last_page = repo.find_page(3, 2).await.unwrap();
+        assert_eq!(last_page.items.len(), 1);
+        assert!(!last_page.has_next());
+    }
+
+    #[tokio::test]
+    async fn test_event_sourced_repository_fold_and_replay() {
+        let repo = EventSourcedUserRepository::new(100);
+
+        let alice_id = repo
+            .save(&User::new("Alice".to_string(), "alice@example.com".to_string()))
+            .await
+            .unwrap();
+        let bob_id = repo
+            .save(&User::new("Bob".to_string(), "bob@example.com".to_string()))
+            .await
+            .unwrap();
+
+        let mut renamed = repo.find_by_id(alice_id).await.unwrap().unwrap();
+        renamed.name = "Alicia".to_string();
+        repo.save(&renamed).await.unwrap();
+
+        assert_eq!(
+            repo.find_by_id(alice_id).await.unwrap().unwrap().name,
+            "Alicia"
+        );
+        assert_eq!(repo.find_all().await.unwrap().len(), 2);
+
+        repo.delete(bob_id).await.unwrap();
+        assert!(repo.find_by_id(bob_id).await.unwrap().is_none());
+        assert_eq!(repo.find_all().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_event_sourced_repository_checkpoints_across_boundary() {
+        // checkpoint_interval of 2 forces a checkpoint partway through this test,
+        // so later reads must fold the checkpoint together with the post-checkpoint log.
+        let repo = EventSourcedUserRepository::new(2);
+
+        let alice_id = repo
+            .save(&User::new("Alice".to_string(), "alice@example.com".to_string()))
+            .await
+            .unwrap();
+        let bob_id = repo
+            .save(&User::new("Bob".to_string(), "bob@example.com".to_string()))
+            .await
+            .unwrap();
+        assert_eq!(repo.checkpoint.lock().unwrap().as_ref().unwrap().last_sequence, 1);
+
+        let carol_id = repo
+            .save(&User::new("Carol".to_string(), "carol@example.com".to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(repo.find_all().await.unwrap().len(), 3);
+        assert!(repo.find_by_id(alice_id).await.unwrap().is_some());
+        assert!(repo.find_by_id(bob_id).await.unwrap().is_some());
+        assert!(repo.find_by_id(carol_id).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_event_sourced_repository_never_reuses_deleted_id() {
+        let repo = EventSourcedUserRepository::new(100);
+
+        let first_id = repo
+            .save(&User::new("Alice".to_string(), "alice@example.com".to_string()))
+            .await
+            .unwrap();
+        repo.delete(first_id).await.unwrap();
+
+        let second_id = repo
+            .save(&User::new("Bob".to_string(), "bob@example.com".to_string()))
+            .await
+            .unwrap();
+
+        assert_ne!(first_id, second_id);
+    }
+
+    #[test]
+    fn test_cache_layer_lru_eviction() {
+        let mut cache: CacheLayer<u64, String> = CacheLayer::new(2, Duration::from_secs(60));
+
+        cache.insert(1, "one".to_string());
+        cache.insert(2, "two".to_string());
+        assert_eq!(CacheLayer::get(&mut cache, &1), Some("one".to_string())); // 1 is now most-recently-used
+
+        let displaced = cache.insert(3, "three".to_string());
+        assert_eq!(displaced, vec![(2, "two".to_string())]); // 2 was least-recently-used
+        assert_eq!(CacheLayer::get(&mut cache, &2), None);
+        assert_eq!(CacheLayer::get(&mut cache, &1), Some("one".to_string()));
+        assert_eq!(CacheLayer::get(&mut cache, &3), Some("three".to_string()));
+    }
+
+    #[test]
+    fn test_cache_layer_ttl_expiry() {
+        let mut cache: CacheLayer<u64, String> = CacheLayer::new(10, Duration::from_millis(10));
+
+        cache.insert(1, "one".to_string());
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(CacheLayer::get(&mut cache, &1), None);
+    }
+
+    #[test]
+    fn test_user_cache_token_lockstep_on_expiry() {
+        let mut cache = UserCache::new(10, Duration::from_millis(10));
+        let mut user = User::new("Alice".to_string(), "alice@example.com".to_string());
+        user.id = 1;
+        user.token = Some("tok-1".to_string());
+        cache.insert(1, user);
+
+        std::thread::sleep(Duration::from_millis(20));
+
+        assert!(cache.get(1).is_none());
+        // The by_id entry expired; by_token must not still resolve to it.
+        assert!(cache.get_by_token("tok-1").is_none());
+    }
+
+    #[test]
+    fn test_user_cache_reinsert_same_token_stays_resolvable() {
+        let mut cache = UserCache::new(10, Duration::from_secs(60));
+        let mut user = User::new("Dana".to_string(), "dana@example.com".to_string());
+        user.id = 1;
+        user.token = Some("tok-3".to_string());
+        cache.insert(1, user.clone());
+
+        // Re-caching the same id with an unchanged token (e.g. a refresh after
+        // a concurrent miss) must not purge the token mapping it just wrote.
+        cache.insert(1, user);
+        assert!(cache.get_by_token("tok-3").is_some());
+    }
+
+    #[test]
+    fn test_user_cache_invalidate_and_clear() {
+        let mut cache = UserCache::new(10, Duration::from_secs(60));
+        let mut user = User::new("Bob".to_string(), "bob@example.com".to_string());
+        user.id = 1;
+        user.token = Some("tok-2".to_string());
+        cache.insert(1, user);
+
+        cache.invalidate(1);
+        assert!(cache.get(1).is_none());
+        assert!(cache.get_by_token("tok-2").is_none());
+
+        let mut other = User::new("Carol".to_string(), "carol@example.com".to_string());
+        other.id = 2;
+        cache.insert(2, other);
+        cache.clear();
+        assert!(cache.get(2).is_none());
+    }
+}
\ No newline at end of file